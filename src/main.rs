@@ -1,12 +1,17 @@
 use clap::{Parser, Subcommand};
+use regex::Regex;
+use std::collections::HashSet;
 use std::fs;
-use std::io::{self, BufRead};
-use std::path::PathBuf;
-use std::process::Command;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 const HOSTS_FILE: &str = "/etc/hosts";
 const BLOCK_MARKER_START: &str = "# FOCUS-MODE-BLOCK START";
 const BLOCK_MARKER_END: &str = "# FOCUS-MODE-BLOCK END";
+const DAEMON_POLL_SECS: u64 = 5;
+const DEFAULT_PROFILE: &str = "default";
 
 #[derive(Parser)]
 #[command(name = "focus")]
@@ -19,13 +24,47 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Enable focus mode - block all configured domains
-    On,
+    On {
+        /// Automatically end the session after a duration, e.g. "25m", "1h30m"
+        #[arg(long = "for")]
+        for_duration: Option<String>,
+        /// Automatically end the session at a wall-clock time, e.g. "17:00"
+        #[arg(long)]
+        until: Option<String>,
+        /// Comma-separated profile(s) to block, e.g. "work,social" (default: "default")
+        #[arg(long)]
+        profile: Option<String>,
+    },
     /// Disable focus mode - unblock all domains
-    Off,
+    Off {
+        /// Comma-separated profile(s) to unblock; omit to unblock everything
+        #[arg(long)]
+        profile: Option<String>,
+    },
     /// Edit the list of blocked domains
-    Edit,
+    Edit {
+        /// Profile to edit (default: "default")
+        #[arg(long)]
+        profile: Option<String>,
+    },
     /// Show current status and blocked domains
-    Status,
+    Status {
+        /// Show only this profile's domains instead of every configured profile
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Extend a running timed session by an additional duration
+    Extend {
+        /// Amount of time to add, e.g. "10m"
+        duration: String,
+    },
+    /// Reinstate /etc/hosts from the pre-focus backup (use if markers get mangled)
+    Restore,
+    /// Fetch and cache every '# SOURCE:' remote blocklist referenced by a profile
+    Sync,
+    /// Internal: background process that ends a timed session (not for direct use)
+    #[command(hide = true, name = "__daemon")]
+    Daemon,
 }
 
 fn get_focus_dir() -> PathBuf {
@@ -33,19 +72,27 @@ fn get_focus_dir() -> PathBuf {
     home.join(".focus")
 }
 
-fn get_domains_file() -> PathBuf {
-    get_focus_dir().join("domains.txt")
+fn get_profiles_dir() -> PathBuf {
+    get_focus_dir().join("profiles")
+}
+
+fn get_domains_file(profile: &str) -> PathBuf {
+    get_profiles_dir().join(format!("{}.txt", profile))
+}
+
+fn get_schedule_file() -> PathBuf {
+    get_focus_dir().join("schedule.json")
 }
 
 fn ensure_focus_dir() -> io::Result<()> {
-    let focus_dir = get_focus_dir();
-    if !focus_dir.exists() {
-        fs::create_dir_all(&focus_dir)?;
+    let profiles_dir = get_profiles_dir();
+    if !profiles_dir.exists() {
+        fs::create_dir_all(&profiles_dir)?;
     }
 
-    let domains_file = get_domains_file();
-    if !domains_file.exists() {
-        // Create default domains file
+    let default_file = get_domains_file(DEFAULT_PROFILE);
+    if !default_file.exists() {
+        // Seed the default profile with a starter list
         let default_domains = "# Add one domain per line\n\
                               # Lines starting with # are comments\n\
                               # Example:\n\
@@ -57,24 +104,488 @@ fn ensure_focus_dir() -> io::Result<()> {
                               www.x.com\n\
                               twitter.com\n\
                               www.twitter.com\n";
-        fs::write(&domains_file, default_domains)?;
+        fs::write(&default_file, default_domains)?;
     }
     Ok(())
 }
 
-fn read_domains() -> io::Result<Vec<String>> {
-    let domains_file = get_domains_file();
-    let file = fs::File::open(&domains_file)?;
-    let reader = io::BufReader::new(file);
+/// Splits a `--profile a,b` argument into names, defaulting to the default
+/// profile when omitted entirely. Errors rather than silently returning an
+/// empty list when the argument is present but blank (e.g. `--profile ","`),
+/// since callers assume at least one profile name comes back.
+fn parse_profile_arg(arg: &Option<String>) -> Result<Vec<String>, String> {
+    match arg {
+        Some(s) => {
+            let profiles: Vec<String> = s
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect();
+            if profiles.is_empty() {
+                return Err(format!("no profile name found in '--profile {}'", s));
+            }
+            Ok(profiles)
+        }
+        None => Ok(vec![DEFAULT_PROFILE.to_string()]),
+    }
+}
+
+/// Whether a profile's entries are things to block (the original behavior)
+/// or the only things to allow, set via a leading `# MODE: allowlist` line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ProfileMode {
+    Denylist,
+    Allowlist,
+}
+
+/// The resolved block for one profile: which domains end up blocked, plus
+/// enough bookkeeping for `focus status` to report how much subdomain/regex
+/// expansion happened.
+struct ProfileResolution {
+    mode: ProfileMode,
+    domains: Vec<String>,
+    raw_entries: usize,
+    expanded_count: usize,
+    sources: Vec<SourceStatus>,
+}
+
+/// Per-source reporting for `focus status`: how many domains it contributed
+/// and when it was last synced (0 if never).
+struct SourceStatus {
+    url: String,
+    domain_count: usize,
+    fetched_at: u64,
+}
+
+/// Read a profile file's directive and entry lines. A first line of
+/// `# MODE: allowlist` (ASCII case-insensitive, surrounding whitespace
+/// ignored) switches the profile into allowlist mode; it is
+/// consumed here rather than treated as an ordinary comment.
+fn read_profile_entries(path: &Path) -> io::Result<(ProfileMode, Vec<String>, Vec<String>)> {
+    let content = fs::read_to_string(path)?;
+    let mut mode = ProfileMode::Denylist;
+    let mut entries = Vec::new();
+    let mut sources = Vec::new();
+
+    for (i, raw_line) in content.lines().enumerate() {
+        let line = raw_line.trim();
+        if i == 0 && line.eq_ignore_ascii_case("# MODE: allowlist") {
+            mode = ProfileMode::Allowlist;
+            continue;
+        }
+        if let Some(url) = line.strip_prefix("# SOURCE:") {
+            sources.push(url.trim().to_string());
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        entries.push(line.to_string());
+    }
+
+    Ok((mode, entries, sources))
+}
+
+/// The full set of literal (non-`re:`) domains known across every profile,
+/// including their synced remote sources, expanded the same way `focus on`
+/// would. This is the candidate pool that `re:` patterns are matched
+/// against, since a regex has nothing concrete to match against on its own.
+fn build_candidate_corpus(prefixes: &[String]) -> io::Result<Vec<String>> {
+    let mut seen = HashSet::new();
+    let mut corpus = Vec::new();
+
+    for profile in list_profiles()? {
+        let (_, entries, sources) = read_profile_entries(&get_domains_file(&profile))?;
+        for entry in entries {
+            if entry.starts_with("re:") {
+                continue;
+            }
+            for expanded in expand_domain_entry(&entry, prefixes) {
+                if seen.insert(expanded.clone()) {
+                    corpus.push(expanded);
+                }
+            }
+        }
+        for url in sources {
+            if let Some(cached) = read_cached_source(&url)? {
+                for domain in cached.domains {
+                    if seen.insert(domain.clone()) {
+                        corpus.push(domain);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(corpus)
+}
+
+fn match_regex_entries(pattern: &str, corpus: &[String]) -> io::Result<Vec<String>> {
+    let re = Regex::new(pattern)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid regex '{}': {}", pattern, e)))?;
+    Ok(corpus.iter().filter(|host| re.is_match(host)).cloned().collect())
+}
+
+/// Resolve one profile's file into the concrete domains it blocks.
+///
+/// In denylist mode (the default) every entry is blocked directly: literal
+/// domains expand to their subdomains as usual, and `re:` entries match
+/// against the full domain corpus. In allowlist mode, literal entries are
+/// the allowed set and `re:` entries are deny-patterns matched against the
+/// corpus - matching precedence is explicit allow beats regex deny, so a
+/// host never gets blocked just because a broad pattern happens to catch it.
+fn resolve_profile(
+    profile: &str,
+    prefixes: &[String],
+    corpus: &[String],
+) -> io::Result<ProfileResolution> {
+    let path = get_domains_file(profile);
+    if !path.exists() {
+        eprintln!(
+            "Warning: profile '{}' has no domains file ({})",
+            profile,
+            path.display()
+        );
+        return Ok(ProfileResolution {
+            mode: ProfileMode::Denylist,
+            domains: Vec::new(),
+            raw_entries: 0,
+            expanded_count: 0,
+            sources: Vec::new(),
+        });
+    }
+
+    let (mode, entries, source_urls) = read_profile_entries(&path)?;
+    let mut seen = HashSet::new();
+    let mut domains = Vec::new();
+    let mut sources = Vec::new();
+
+    for url in &source_urls {
+        let cached = read_cached_source(url)?;
+        let domain_count = cached.as_ref().map(|c| c.domains.len()).unwrap_or(0);
+        let fetched_at = cached.as_ref().map(|c| c.fetched_at).unwrap_or(0);
+        sources.push(SourceStatus {
+            url: url.clone(),
+            domain_count,
+            fetched_at,
+        });
+        if mode == ProfileMode::Denylist {
+            if let Some(cached) = cached {
+                for host in cached.domains {
+                    if seen.insert(host.clone()) {
+                        domains.push(host);
+                    }
+                }
+            }
+        }
+    }
+
+    match mode {
+        ProfileMode::Denylist => {
+            for entry in &entries {
+                let matches = match entry.strip_prefix("re:") {
+                    Some(pattern) => match_regex_entries(pattern, corpus)?,
+                    None => expand_domain_entry(entry, prefixes),
+                };
+                for host in matches {
+                    if seen.insert(host.clone()) {
+                        domains.push(host);
+                    }
+                }
+            }
+        }
+        ProfileMode::Allowlist => {
+            let mut allow = HashSet::new();
+            let mut deny_patterns = Vec::new();
+            for entry in &entries {
+                match entry.strip_prefix("re:") {
+                    Some(pattern) => deny_patterns.push(pattern.to_string()),
+                    None => allow.extend(expand_domain_entry(entry, prefixes)),
+                }
+            }
+
+            for pattern in &deny_patterns {
+                for host in match_regex_entries(pattern, corpus)? {
+                    if allow.contains(&host) {
+                        continue;
+                    }
+                    if seen.insert(host.clone()) {
+                        domains.push(host);
+                    }
+                }
+            }
+        }
+    }
+
+    let raw_entries = entries.len();
+    let expanded_count = domains.len().saturating_sub(raw_entries);
+    Ok(ProfileResolution {
+        mode,
+        domains,
+        raw_entries,
+        expanded_count,
+        sources,
+    })
+}
 
-    let domains: Vec<String> = reader
-        .lines()
-        .filter_map(|line| line.ok())
-        .map(|line| line.trim().to_string())
+/// Read and union the domain lists for a set of profiles, de-duplicating
+/// while preserving first-seen order.
+fn read_domains_for_profiles(profiles: &[String]) -> io::Result<Vec<String>> {
+    let prefixes = load_subdomain_prefixes();
+    let corpus = build_candidate_corpus(&prefixes)?;
+
+    let mut seen = HashSet::new();
+    let mut domains = Vec::new();
+
+    for profile in profiles {
+        let resolution = resolve_profile(profile, &prefixes, &corpus)?;
+        for domain in resolution.domains {
+            if seen.insert(domain.clone()) {
+                domains.push(domain);
+            }
+        }
+    }
+
+    Ok(domains)
+}
+
+/// A bare apex domain (exactly one dot, e.g. "instagram.com") or an explicit
+/// `*.instagram.com` entry is treated as a whole-domain block: it expands to
+/// the apex plus a set of common subdomains. Anything else (e.g.
+/// "www.instagram.com") is used as a literal, unexpanded entry.
+fn expand_domain_entry(entry: &str, prefixes: &[String]) -> Vec<String> {
+    let apex = if let Some(rest) = entry.strip_prefix("*.") {
+        Some(rest)
+    } else if entry.matches('.').count() == 1 {
+        Some(entry)
+    } else {
+        None
+    };
+
+    match apex {
+        Some(apex) => {
+            let mut expanded = vec![apex.to_string()];
+            for prefix in prefixes {
+                expanded.push(format!("{}.{}", prefix, apex));
+            }
+            expanded
+        }
+        None => vec![entry.to_string()],
+    }
+}
+
+fn get_sources_dir() -> PathBuf {
+    get_focus_dir().join("sources")
+}
+
+/// A cached copy of a `# SOURCE:` blocklist, parsed and de-duplicated.
+struct CachedSource {
+    domains: Vec<String>,
+    fetched_at: u64,
+}
+
+/// Deterministic on-disk paths for a source URL's cache file and the ETag
+/// file `curl` uses to make repeat syncs conditional GETs.
+fn source_cache_paths(url: &str) -> (PathBuf, PathBuf) {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    let hash = hasher.finish();
+
+    let sources_dir = get_sources_dir();
+    (
+        sources_dir.join(format!("{:016x}.txt", hash)),
+        sources_dir.join(format!("{:016x}.etag", hash)),
+    )
+}
+
+/// Parse a hosts-format (or plain domain-per-line) blocklist, accepting
+/// `0.0.0.0 domain` / `127.0.0.1 domain` lines as well as bare domains, and
+/// stripping comments.
+fn parse_hosts_format(raw: &str) -> Vec<String> {
+    raw.lines()
+        .map(|line| line.trim())
         .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let first = fields.next()?;
+            if first == "0.0.0.0" || first == "127.0.0.1" {
+                fields.next().map(|d| d.to_string())
+            } else {
+                Some(first.to_string())
+            }
+        })
+        .collect()
+}
+
+/// Download a `# SOURCE:` blocklist with `curl`, using its built-in
+/// conditional-GET support so repeated syncs are cheap, then cache the
+/// parsed, de-duplicated domain list to disk.
+fn fetch_source(url: &str) -> io::Result<CachedSource> {
+    let (cache_path, etag_path) = source_cache_paths(url);
+    fs::create_dir_all(get_sources_dir())?;
+    let body_path = cache_path.with_extension("raw");
+
+    let status = Command::new("curl")
+        .args([
+            "-fsSL",
+            "--etag-compare",
+            etag_path.to_str().unwrap_or_default(),
+            "--etag-save",
+            etag_path.to_str().unwrap_or_default(),
+            "-o",
+            body_path.to_str().unwrap_or_default(),
+            url,
+        ])
+        .status();
+
+    let fetched_ok = matches!(status, Ok(s) if s.success());
+    if !fetched_ok {
+        // Never treat a failed curl run as a sync: leave the existing cache
+        // (and its FETCHED-AT) untouched so "focus status" keeps reporting
+        // the real last-successful-sync time instead of "just now".
+        return Err(io::Error::other(if cache_path.exists() {
+            format!(
+                "failed to fetch source '{}'; keeping previously cached copy",
+                url
+            )
+        } else {
+            format!("failed to fetch source '{}'", url)
+        }));
+    }
+
+    let raw = fs::read_to_string(&body_path)?;
+    let mut seen = HashSet::new();
+    let domains: Vec<String> = parse_hosts_format(&raw)
+        .into_iter()
+        .filter(|d| seen.insert(d.clone()))
         .collect();
 
-    Ok(domains)
+    let fetched_at = now_epoch();
+    let mut cache_content = format!("# FETCHED-AT: {}\n", fetched_at);
+    for domain in &domains {
+        cache_content.push_str(domain);
+        cache_content.push('\n');
+    }
+    fs::write(&cache_path, cache_content)?;
+
+    Ok(CachedSource {
+        domains,
+        fetched_at,
+    })
+}
+
+/// Read a previously synced source's cache without touching the network.
+fn read_cached_source(url: &str) -> io::Result<Option<CachedSource>> {
+    let (cache_path, _) = source_cache_paths(url);
+    if !cache_path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&cache_path)?;
+    let mut fetched_at = 0u64;
+    let mut domains = Vec::new();
+    for line in content.lines() {
+        if let Some(rest) = line.strip_prefix("# FETCHED-AT:") {
+            fetched_at = rest.trim().parse().unwrap_or(0);
+        } else if !line.is_empty() && !line.starts_with('#') {
+            domains.push(line.to_string());
+        }
+    }
+
+    Ok(Some(CachedSource {
+        domains,
+        fetched_at,
+    }))
+}
+
+/// Load the subdomain prefixes used to expand whole-domain blocks, from
+/// `~/.focus/subdomains.txt` if present, otherwise a sensible built-in list.
+fn load_subdomain_prefixes() -> Vec<String> {
+    let path = get_focus_dir().join("subdomains.txt");
+    if let Ok(content) = fs::read_to_string(&path) {
+        let prefixes: Vec<String> = content
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .collect();
+        if !prefixes.is_empty() {
+            return prefixes;
+        }
+    }
+
+    ["www", "m", "mobile", "api", "cdn", "static", "graph"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// List the names of every profile that has a domains file on disk.
+fn list_profiles() -> io::Result<Vec<String>> {
+    let profiles_dir = get_profiles_dir();
+    if !profiles_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut profiles: Vec<String> = fs::read_dir(&profiles_dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) == Some("txt") {
+                path.file_stem()
+                    .and_then(|s| s.to_str())
+                    .map(|s| s.to_string())
+            } else {
+                None
+            }
+        })
+        .collect();
+    profiles.sort();
+    Ok(profiles)
+}
+
+fn get_hosts_backup_file() -> PathBuf {
+    get_focus_dir().join("hosts.bak")
+}
+
+/// Snapshot the current `/etc/hosts` the first time we're about to mutate it
+/// in a session, so `focus restore` always has an untouched copy to fall
+/// back to. A no-op while a session is active and the backup already exists.
+fn ensure_hosts_backup() -> io::Result<()> {
+    let backup = get_hosts_backup_file();
+    if backup.exists() && is_focus_active() {
+        return Ok(());
+    }
+    let content = fs::read_to_string(HOSTS_FILE)?;
+    fs::create_dir_all(get_focus_dir())?;
+    fs::write(&backup, content)
+}
+
+/// Write new hosts-file content atomically: write to a sibling temp file,
+/// fsync it, then rename over `/etc/hosts` so a crash or interrupted write
+/// can never leave the file truncated or half-written.
+fn write_hosts_atomic(content: &str) -> io::Result<()> {
+    let hosts_path = Path::new(HOSTS_FILE);
+    let dir = hosts_path.parent().unwrap_or_else(|| Path::new("/etc"));
+    let tmp_path = dir.join("hosts.focus.tmp");
+
+    // A freshly created file takes the process umask, not the original
+    // file's mode - carry that over explicitly so a strict umask doesn't
+    // leave /etc/hosts root-only after the rename.
+    let original_permissions = fs::metadata(hosts_path).ok().map(|m| m.permissions());
+
+    let mut tmp_file = fs::File::create(&tmp_path)?;
+    tmp_file.write_all(content.as_bytes())?;
+    tmp_file.sync_all()?;
+    drop(tmp_file);
+
+    if let Some(permissions) = original_permissions {
+        fs::set_permissions(&tmp_path, permissions)?;
+    }
+
+    fs::rename(&tmp_path, hosts_path)
 }
 
 fn is_focus_active() -> bool {
@@ -85,96 +596,641 @@ fn is_focus_active() -> bool {
     }
 }
 
-fn focus_on() -> io::Result<()> {
+/// Parse the profile list out of the active `# FOCUS-MODE-BLOCK START [...]`
+/// marker line, e.g. `[work,social]` -> `["work", "social"]`.
+fn active_profiles() -> Option<Vec<String>> {
+    let content = fs::read_to_string(HOSTS_FILE).ok()?;
+    for line in content.lines() {
+        if line.contains(BLOCK_MARKER_START) {
+            let start = line.find('[')?;
+            let end = line.find(']')?;
+            return Some(
+                line[start + 1..end]
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+            );
+        }
+    }
+    None
+}
+
+fn build_block(profiles: &[String], domains: &[String]) -> String {
+    let label = profiles.join(",");
+    let mut block = String::new();
+    block.push('\n');
+    block.push_str(&format!("{} [{}]\n", BLOCK_MARKER_START, label));
+    for domain in domains {
+        block.push_str(&format!("127.0.0.1 {}\n", domain));
+    }
+    block.push_str(&format!("{} [{}]\n", BLOCK_MARKER_END, label));
+    block
+}
+
+/// Remove any existing `FOCUS-MODE-BLOCK` section(s) from hosts-file content.
+/// Tracks marker depth rather than a plain in/out flag so nested or duplicate
+/// START/END pairs (a mangled hosts file) collapse into a single removed span
+/// instead of leaking stray lines back into the file.
+fn strip_block(content: &str) -> String {
+    let mut new_content = String::new();
+    let mut depth: u32 = 0;
+
+    for line in content.lines() {
+        if line.contains(BLOCK_MARKER_START) {
+            depth += 1;
+            continue;
+        }
+        if line.contains(BLOCK_MARKER_END) {
+            depth = depth.saturating_sub(1);
+            continue;
+        }
+        if depth == 0 {
+            new_content.push_str(line);
+            new_content.push('\n');
+        }
+    }
+
+    new_content.trim_end().to_string() + "\n"
+}
+
+fn now_epoch() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// A scheduled end-time for a timed focus session, persisted so `focus status`
+/// can report remaining time and a crashed daemon can be recovered. Tracks
+/// which profiles the timer applies to, so the daemon only ever tears down
+/// the session it was scheduled for - not every profile that happens to be
+/// active when the timer fires.
+struct Schedule {
+    domains: Vec<String>,
+    profiles: Vec<String>,
+    end_epoch: u64,
+}
+
+fn json_string_array(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|v| format!("\"{}\"", v.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn parse_json_string_array(content: &str, key: &str) -> Vec<String> {
+    content
+        .split(&format!("\"{}\":[", key))
+        .nth(1)
+        .and_then(|rest| rest.split(']').next())
+        .map(|list| {
+            list.split(',')
+                .map(|s| {
+                    // Strip exactly one pair of wrapping quotes rather than
+                    // `trim_matches('"')`, which also eats the closing quote
+                    // of a field whose content itself ends in an escaped `\"`.
+                    let s = s.trim();
+                    let inner = s
+                        .strip_prefix('"')
+                        .and_then(|s| s.strip_suffix('"'))
+                        .unwrap_or(s);
+                    inner.replace("\\\"", "\"").replace("\\\\", "\\")
+                })
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn write_schedule(schedule: &Schedule) -> io::Result<()> {
+    let json = format!(
+        "{{\"domains\":[{}],\"profiles\":[{}],\"end_epoch\":{}}}\n",
+        json_string_array(&schedule.domains),
+        json_string_array(&schedule.profiles),
+        schedule.end_epoch
+    );
+    fs::write(get_schedule_file(), json)
+}
+
+fn read_schedule() -> io::Result<Option<Schedule>> {
+    let path = get_schedule_file();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)?;
+
+    let end_epoch = content
+        .split("\"end_epoch\":")
+        .nth(1)
+        .and_then(|rest| rest.trim_start().split(|c: char| !c.is_ascii_digit()).next())
+        .and_then(|digits| digits.parse::<u64>().ok())
+        .unwrap_or(0);
+
+    let domains = parse_json_string_array(&content, "domains");
+    let profiles = parse_json_string_array(&content, "profiles");
+
+    Ok(Some(Schedule {
+        domains,
+        profiles,
+        end_epoch,
+    }))
+}
+
+fn remove_schedule() -> io::Result<()> {
+    let path = get_schedule_file();
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Parse a short duration string like "25m", "1h30m" or "90s" into seconds.
+fn parse_duration_secs(input: &str) -> Result<u64, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("empty duration".to_string());
+    }
+
+    let mut total = 0u64;
+    let mut number = String::new();
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+        } else {
+            if number.is_empty() {
+                return Err(format!("invalid duration: {}", input));
+            }
+            let value: u64 = number
+                .parse()
+                .map_err(|_| format!("invalid duration: {}", input))?;
+            number.clear();
+            let multiplier = match c {
+                's' => 1,
+                'm' => 60,
+                'h' => 3600,
+                _ => return Err(format!("unknown duration unit '{}' in '{}'", c, input)),
+            };
+            let seconds = value
+                .checked_mul(multiplier)
+                .ok_or_else(|| format!("invalid duration: {}", input))?;
+            total = total
+                .checked_add(seconds)
+                .ok_or_else(|| format!("invalid duration: {}", input))?;
+        }
+    }
+    if !number.is_empty() {
+        return Err(format!(
+            "duration '{}' is missing a unit (s/m/h)",
+            input
+        ));
+    }
+    Ok(total)
+}
+
+/// Parses a wall-clock time like "17:00" or "17:00:30" and returns the next
+/// epoch at which that local time occurs (today if it hasn't passed yet,
+/// otherwise tomorrow).
+///
+/// `date -d <input> +%s` is GNU-date-only syntax with no equivalent flag on
+/// BSD date (macOS) or Windows, so this parses the clock time itself and
+/// only asks the system for the local
+/// date/UTC-offset, using a `date`/PowerShell invocation whose output format
+/// (not a parsing flag) both platforms agree on.
+fn parse_until_epoch(input: &str) -> Result<u64, String> {
+    let (hour, minute, second) =
+        parse_clock_time(input).ok_or_else(|| format!("could not parse time '{}'", input))?;
+
+    let (year, month, day, offset_secs) = local_date_and_offset()?;
+    let days = days_from_civil(year, month, day);
+    let target_local_secs = (hour * 3600 + minute * 60 + second) as i64;
+
+    let mut epoch = days * 86_400 + target_local_secs - offset_secs;
+
+    let now = now_epoch() as i64;
+    if epoch <= now {
+        epoch += 24 * 3600;
+    }
+
+    u64::try_from(epoch).map_err(|_| format!("could not parse time '{}'", input))
+}
+
+fn parse_clock_time(input: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = input.trim().splitn(3, ':');
+    let hour: u32 = parts.next()?.parse().ok()?;
+    let minute: u32 = parts.next()?.parse().ok()?;
+    let second: u32 = match parts.next() {
+        Some(s) => s.parse().ok()?,
+        None => 0,
+    };
+    if hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    Some((hour, minute, second))
+}
+
+/// Days since the Unix epoch (1970-01-01) for a civil (proleptic Gregorian)
+/// date. Howard Hinnant's well-known constant-time algorithm - used here
+/// instead of a date/time crate dependency for a single calculation.
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400; // [0, 399]
+    let mp = (m + 9) % 12; // [0, 11]
+    let doy = (153 * mp + 2) / 5 + d - 1; // [0, 365]
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy; // [0, 146096]
+    era * 146_097 + doe - 719_468
+}
+
+/// Returns (year, month, day, utc_offset_seconds) for the local date/time
+/// "now", asked of the OS rather than computed, since Rust's standard
+/// library has no portable way to read the local UTC offset.
+fn local_date_and_offset() -> Result<(i64, i64, i64, i64), String> {
+    let output = match std::env::consts::OS {
+        "windows" => Command::new("powershell")
+            .args([
+                "-NoProfile",
+                "-Command",
+                "Get-Date -Format 'yyyy-MM-dd HH:mm:ss zzz'",
+            ])
+            .output(),
+        _ => Command::new("date").args(["+%Y-%m-%d %H:%M:%S %z"]).output(),
+    }
+    .map_err(|e| format!("failed to read local time: {}", e))?;
+
+    if !output.status.success() {
+        return Err("failed to read local time".to_string());
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    parse_local_date_and_offset(text.trim())
+        .ok_or_else(|| format!("could not parse local time output: '{}'", text.trim()))
+}
+
+fn parse_local_date_and_offset(text: &str) -> Option<(i64, i64, i64, i64)> {
+    let mut fields = text.split_whitespace();
+    let date = fields.next()?;
+    let _time = fields.next()?;
+    let offset = fields.next()?;
+
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: i64 = date_parts.next()?.parse().ok()?;
+    let day: i64 = date_parts.next()?.parse().ok()?;
+
+    let offset_secs = parse_utc_offset(offset)?;
+    Some((year, month, day, offset_secs))
+}
+
+/// Accepts "+HHMM"/"-HHMM" (GNU/BSD `date %z`) or "+HH:MM" (PowerShell `zzz`).
+fn parse_utc_offset(offset: &str) -> Option<i64> {
+    let cleaned = offset.replace(':', "");
+    if cleaned.len() != 5 {
+        return None;
+    }
+    let sign: i64 = match &cleaned[0..1] {
+        "+" => 1,
+        "-" => -1,
+        _ => return None,
+    };
+    let hours: i64 = cleaned[1..3].parse().ok()?;
+    let minutes: i64 = cleaned[3..5].parse().ok()?;
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+fn format_remaining(end_epoch: u64) -> String {
+    let now = now_epoch();
+    if end_epoch <= now {
+        return "0s".to_string();
+    }
+    let remaining = end_epoch - now;
+    let hours = remaining / 3600;
+    let minutes = (remaining % 3600) / 60;
+    let seconds = remaining % 60;
+
+    if hours > 0 {
+        format!("{}h{}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m{}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Format how long ago an epoch timestamp was, for `focus status`'s
+/// "last synced" display. Zero means never synced.
+fn format_ago(epoch: u64) -> String {
+    if epoch == 0 {
+        return "never".to_string();
+    }
+    let now = now_epoch();
+    if epoch >= now {
+        return "just now".to_string();
+    }
+    let elapsed = now - epoch;
+    let hours = elapsed / 3600;
+    let minutes = (elapsed % 3600) / 60;
+
+    if hours > 0 {
+        format!("{}h{}m ago", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m ago", minutes)
+    } else {
+        format!("{}s ago", elapsed)
+    }
+}
+
+/// Recover from a daemon that died before it could unblock: if a schedule is
+/// on disk whose end-time has already passed, turn focus off and clear it.
+fn recover_expired_schedule() {
+    if let Ok(Some(schedule)) = read_schedule() {
+        if schedule.end_epoch <= now_epoch() && is_focus_active() {
+            let scope = if schedule.profiles.is_empty() {
+                None
+            } else {
+                Some(schedule.profiles.join(","))
+            };
+            if let Err(e) = focus_off(scope) {
+                eprintln!("Error recovering from crashed focus session: {}", e);
+            }
+            let _ = remove_schedule();
+        }
+    }
+}
+
+fn spawn_daemon() -> io::Result<()> {
+    let exe = std::env::current_exe()?;
+    let mut cmd = Command::new(exe);
+    cmd.arg("__daemon")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    // Null stdio alone isn't enough: the child still belongs to the
+    // terminal's session/process group, so closing the terminal (or it
+    // exiting) sends SIGHUP to the daemon and kills the scheduled unblock
+    // before it fires. Detach it properly per-platform.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        unsafe {
+            cmd.pre_exec(|| {
+                if setsid() == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const DETACHED_PROCESS: u32 = 0x0000_0008;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        cmd.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+    }
+
+    cmd.spawn()?;
+    Ok(())
+}
+
+#[cfg(unix)]
+extern "C" {
+    fn setsid() -> i32;
+}
+
+fn run_daemon() -> io::Result<()> {
+    loop {
+        let schedule = match read_schedule()? {
+            Some(schedule) => schedule,
+            None => return Ok(()),
+        };
+
+        if now_epoch() >= schedule.end_epoch {
+            if is_focus_active() {
+                // Only unblock the profile(s) this timer was scheduled for -
+                // other profiles the user turned on separately (with or
+                // without their own timer) must stay untouched.
+                let scope = if schedule.profiles.is_empty() {
+                    None
+                } else {
+                    Some(schedule.profiles.join(","))
+                };
+                focus_off(scope)?;
+            }
+            remove_schedule()?;
+            return Ok(());
+        }
+
+        std::thread::sleep(std::time::Duration::from_secs(DAEMON_POLL_SECS));
+    }
+}
+
+fn focus_on(
+    profile: Option<String>,
+    for_duration: Option<String>,
+    until: Option<String>,
+) -> io::Result<()> {
+    let requested = match parse_profile_arg(&profile) {
+        Ok(requested) => requested,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
     if is_focus_active() {
-        println!("Focus mode is already active.");
+        if for_duration.is_some() || until.is_some() {
+            eprintln!(
+                "Error: --for/--until can't be used while merging into an already-active focus \
+                 session - a timer can only track the profile(s) it was scheduled for. Run \
+                 'focus off' first if you want to start a new timed session."
+            );
+            std::process::exit(1);
+        }
+
+        let existing = active_profiles().unwrap_or_default();
+        let mut merged = existing.clone();
+        for p in &requested {
+            if !merged.contains(p) {
+                merged.push(p.clone());
+            }
+        }
+
+        if merged == existing {
+            println!(
+                "Focus mode is already active for profile(s): {}",
+                existing.join(", ")
+            );
+            return Ok(());
+        }
+
+        let domains = read_domains_for_profiles(&merged)?;
+        ensure_hosts_backup()?;
+        let hosts_content = fs::read_to_string(HOSTS_FILE)?;
+        let new_content = strip_block(&hosts_content) + &build_block(&merged, &domains);
+        write_hosts_atomic(&new_content)?;
+        flush_dns_cache_or_warn();
+
+        println!(
+            "Focus mode updated. Now blocking profile(s) {} ({} domains):",
+            merged.join(", "),
+            domains.len()
+        );
+        for domain in &domains {
+            println!("  - {}", domain);
+        }
         return Ok(());
     }
 
-    let domains = read_domains()?;
+    let domains = read_domains_for_profiles(&requested)?;
     if domains.is_empty() {
-        println!("No domains configured. Run 'focus edit' to add domains.");
+        println!(
+            "No domains configured for profile(s) {}. Run 'focus edit --profile {}' to add some.",
+            requested.join(", "),
+            requested[0]
+        );
         return Ok(());
     }
 
-    // Build the block to add
-    let mut block = String::new();
-    block.push('\n');
-    block.push_str(BLOCK_MARKER_START);
-    block.push('\n');
-    for domain in &domains {
-        block.push_str(&format!("127.0.0.1 {}\n", domain));
-    }
-    block.push_str(BLOCK_MARKER_END);
-    block.push('\n');
-
-    // Read current hosts file and append
-    let mut hosts_content = fs::read_to_string(HOSTS_FILE)?;
-    hosts_content.push_str(&block);
+    let end_epoch = match (for_duration, until) {
+        (Some(_), Some(_)) => {
+            eprintln!("Error: --for and --until cannot be used together.");
+            std::process::exit(1);
+        }
+        (Some(duration), None) => match parse_duration_secs(&duration) {
+            Ok(secs) => Some(now_epoch() + secs),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        (None, Some(until)) => match parse_until_epoch(&until) {
+            Ok(epoch) => Some(epoch),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        (None, None) => None,
+    };
 
-    // Write back (requires sudo)
-    fs::write(HOSTS_FILE, hosts_content)?;
+    // Read current hosts file and append the block
+    ensure_hosts_backup()?;
+    let hosts_content = fs::read_to_string(HOSTS_FILE)?;
+    let new_content = hosts_content + &build_block(&requested, &domains);
+    write_hosts_atomic(&new_content)?;
 
     // Flush DNS cache
-    flush_dns_cache();
+    flush_dns_cache_or_warn();
 
-    println!("Focus mode activated. Blocked {} domains:", domains.len());
+    println!(
+        "Focus mode activated for profile(s) {}. Blocked {} domains:",
+        requested.join(", "),
+        domains.len()
+    );
     for domain in &domains {
         println!("  - {}", domain);
     }
 
+    if let Some(end_epoch) = end_epoch {
+        write_schedule(&Schedule {
+            domains: domains.clone(),
+            profiles: requested.clone(),
+            end_epoch,
+        })?;
+        spawn_daemon()?;
+        println!(
+            "Session will end automatically in {}.",
+            format_remaining(end_epoch)
+        );
+    }
+
     Ok(())
 }
 
-fn focus_off() -> io::Result<()> {
+fn focus_off(profile: Option<String>) -> io::Result<()> {
     if !is_focus_active() {
         println!("Focus mode is not active.");
         return Ok(());
     }
 
+    ensure_hosts_backup()?;
     let hosts_content = fs::read_to_string(HOSTS_FILE)?;
+    let active = active_profiles().unwrap_or_default();
 
-    // Remove the focus block
-    let mut new_content = String::new();
-    let mut in_block = false;
-
-    for line in hosts_content.lines() {
-        if line.contains(BLOCK_MARKER_START) {
-            in_block = true;
-            continue;
-        }
-        if line.contains(BLOCK_MARKER_END) {
-            in_block = false;
-            continue;
+    let remaining = match &profile {
+        None => Vec::new(),
+        Some(arg) => {
+            let requested = match parse_profile_arg(&Some(arg.clone())) {
+                Ok(requested) => requested,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            active
+                .iter()
+                .filter(|p| !requested.contains(p))
+                .cloned()
+                .collect()
         }
-        if !in_block {
-            new_content.push_str(line);
-            new_content.push('\n');
+    };
+
+    // A schedule only describes the profile(s) it was created for. Once any
+    // of those is no longer active - whether everything was turned off or
+    // just the scheduled profile was, while others stay active - it no
+    // longer describes reality and must be cleared, not only when the block
+    // is fully empty; otherwise it's stuck reporting an already-expired
+    // timer forever and triggering a spurious rewrite on every command.
+    if let Some(schedule) = read_schedule()? {
+        let scope_intact = if schedule.profiles.is_empty() {
+            !remaining.is_empty()
+        } else {
+            schedule.profiles.iter().all(|p| remaining.contains(p))
+        };
+        if !scope_intact {
+            remove_schedule()?;
         }
     }
 
-    // Remove trailing newlines that we might have added
-    let new_content = new_content.trim_end().to_string() + "\n";
-
-    fs::write(HOSTS_FILE, new_content)?;
-
-    // Flush DNS cache
-    flush_dns_cache();
-
-    println!("Focus mode deactivated. All sites unblocked.");
+    if remaining.is_empty() {
+        let new_content = strip_block(&hosts_content);
+        write_hosts_atomic(&new_content)?;
+        flush_dns_cache_or_warn();
+        println!("Focus mode deactivated. All sites unblocked.");
+    } else {
+        let domains = read_domains_for_profiles(&remaining)?;
+        let new_content = strip_block(&hosts_content) + &build_block(&remaining, &domains);
+        write_hosts_atomic(&new_content)?;
+        flush_dns_cache_or_warn();
+        println!(
+            "Unblocked profile(s) {}. Still blocking: {}.",
+            profile.unwrap_or_default(),
+            remaining.join(", ")
+        );
+    }
 
     Ok(())
 }
 
-fn focus_edit() -> io::Result<()> {
-    let domains_file = get_domains_file();
+fn focus_edit(profile: Option<String>) -> io::Result<()> {
+    let profile = profile.unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+    let domains_file = get_domains_file(&profile);
+
+    fs::create_dir_all(get_profiles_dir())?;
+    if !domains_file.exists() {
+        fs::write(
+            &domains_file,
+            format!("# Add one domain per line for the '{}' profile\n", profile),
+        )?;
+    }
 
     // Get editor from EDITOR env var, fall back to vim
     let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vim".to_string());
 
-    let status = Command::new(&editor)
-        .arg(&domains_file)
-        .status()?;
+    let status = Command::new(&editor).arg(&domains_file).status()?;
 
     if status.success() {
         println!("Domains file saved. Changes will apply next time you run 'focus on'.");
@@ -186,48 +1242,248 @@ fn focus_edit() -> io::Result<()> {
     Ok(())
 }
 
-fn focus_status() -> io::Result<()> {
+fn focus_status(profile: Option<String>) -> io::Result<()> {
     if is_focus_active() {
-        println!("Focus mode: ACTIVE");
+        let active = active_profiles().unwrap_or_default();
+        println!("Focus mode: ACTIVE (profile(s): {})", active.join(", "));
     } else {
         println!("Focus mode: INACTIVE");
     }
 
-    println!("\nConfigured domains ({}):", get_domains_file().display());
-    let domains = read_domains()?;
-    if domains.is_empty() {
-        println!("  (none configured)");
-    } else {
-        for domain in &domains {
-            println!("  - {}", domain);
+    if let Some(schedule) = read_schedule()? {
+        println!("Session ends in: {}", format_remaining(schedule.end_epoch));
+    }
+
+    let profiles = match profile {
+        Some(arg) => match parse_profile_arg(&Some(arg)) {
+            Ok(profiles) => profiles,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => {
+            let all = list_profiles()?;
+            if all.is_empty() {
+                vec![DEFAULT_PROFILE.to_string()]
+            } else {
+                all
+            }
+        }
+    };
+
+    let prefixes = load_subdomain_prefixes();
+    let corpus = build_candidate_corpus(&prefixes)?;
+
+    for profile in &profiles {
+        let domains_file = get_domains_file(profile);
+        let resolution = resolve_profile(profile, &prefixes, &corpus)?;
+        let mode = match resolution.mode {
+            ProfileMode::Denylist => "denylist",
+            ProfileMode::Allowlist => "allowlist",
+        };
+        println!(
+            "\nProfile '{}' [{} mode] ({}):",
+            profile,
+            mode,
+            domains_file.display()
+        );
+        if resolution.domains.is_empty() {
+            println!("  (none configured)");
+        } else {
+            for domain in &resolution.domains {
+                println!("  - {}", domain);
+            }
+        }
+        if resolution.expanded_count > 0 {
+            println!(
+                "  ({} entries expanded/matched to {} blocked domains)",
+                resolution.raw_entries,
+                resolution.domains.len()
+            );
+        }
+        for source in &resolution.sources {
+            println!(
+                "  source {}: {} domains (synced {})",
+                source.url,
+                source.domain_count,
+                format_ago(source.fetched_at)
+            );
         }
     }
 
     Ok(())
 }
 
-fn flush_dns_cache() {
-    // macOS DNS cache flush
-    let _ = Command::new("dscacheutil").arg("-flushcache").status();
-    let _ = Command::new("killall")
-        .args(["-HUP", "mDNSResponder"])
-        .status();
+fn focus_extend(duration: String) -> io::Result<()> {
+    let added = match parse_duration_secs(&duration) {
+        Ok(secs) => secs,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut schedule = match read_schedule()? {
+        Some(schedule) => schedule,
+        None => {
+            println!("No timed session is running. Start one with 'focus on --for <duration>'.");
+            return Ok(());
+        }
+    };
+
+    schedule.end_epoch = schedule.end_epoch.saturating_add(added);
+    write_schedule(&schedule)?;
+    println!(
+        "Session extended. Now ends in {}.",
+        format_remaining(schedule.end_epoch)
+    );
+
+    Ok(())
+}
+
+/// Reinstate `/etc/hosts` from the pre-focus backup, for when the
+/// `FOCUS-MODE-BLOCK` markers get mangled (e.g. by another tool editing the
+/// file) and normal off/on parsing can no longer trust them.
+fn focus_restore() -> io::Result<()> {
+    let backup = get_hosts_backup_file();
+    if !backup.exists() {
+        println!(
+            "No backup found at {}. Nothing to restore.",
+            backup.display()
+        );
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(&backup)?;
+    write_hosts_atomic(&content)?;
+    flush_dns_cache_or_warn();
+    remove_schedule()?;
+
+    println!("Restored /etc/hosts from backup ({}).", backup.display());
+    Ok(())
+}
+
+/// Fetch every `# SOURCE:` blocklist referenced by any profile and refresh
+/// their caches. `focus on`/`focus status` only ever read the cache, so this
+/// is the one command that touches the network.
+fn focus_sync() -> io::Result<()> {
+    let mut synced_any = false;
+
+    for profile in list_profiles()? {
+        let (_, _, sources) = read_profile_entries(&get_domains_file(&profile))?;
+        for url in sources {
+            synced_any = true;
+            match fetch_source(&url) {
+                Ok(cached) => println!(
+                    "[{}] synced {} ({} domains)",
+                    profile,
+                    url,
+                    cached.domains.len()
+                ),
+                Err(e) => eprintln!("[{}] failed to sync {}: {}", profile, url, e),
+            }
+        }
+    }
+
+    if !synced_any {
+        println!("No profiles reference a '# SOURCE:' blocklist. Nothing to sync.");
+    }
+
+    Ok(())
+}
+
+/// Run a command, discarding its output, returning whether it exited
+/// successfully (false if the command couldn't even be found).
+fn try_command(program: &str, args: &[&str]) -> bool {
+    Command::new(program)
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Flush the OS DNS cache so blocked/unblocked domains take effect
+/// immediately instead of waiting out the cache's TTL. Dispatches to the
+/// right mechanism per platform, trying fallbacks in order on Linux where
+/// the resolver setup varies across distros.
+fn flush_dns_cache() -> Result<(), String> {
+    match std::env::consts::OS {
+        "macos" => {
+            let flushed_cache = try_command("dscacheutil", &["-flushcache"]);
+            let restarted_mdns = try_command("killall", &["-HUP", "mDNSResponder"]);
+            if flushed_cache || restarted_mdns {
+                Ok(())
+            } else {
+                Err("dscacheutil and killall mDNSResponder both failed".to_string())
+            }
+        }
+        "linux" => {
+            if try_command("resolvectl", &["flush-caches"]) {
+                return Ok(());
+            }
+            if try_command("systemd-resolve", &["--flush-caches"]) {
+                return Ok(());
+            }
+            if try_command("service", &["nscd", "restart"]) {
+                return Ok(());
+            }
+            Err("no known DNS flush mechanism succeeded (tried resolvectl, systemd-resolve, nscd)".to_string())
+        }
+        "windows" => {
+            if try_command("ipconfig", &["/flushdns"]) {
+                Ok(())
+            } else {
+                Err("ipconfig /flushdns failed".to_string())
+            }
+        }
+        other => Err(format!("no known DNS flush mechanism for platform '{}'", other)),
+    }
+}
+
+/// Flush the DNS cache, warning rather than failing the whole command if no
+/// known mechanism works - the hosts-file change has already taken effect,
+/// only a stale cache entry might delay it.
+fn flush_dns_cache_or_warn() {
+    if let Err(e) = flush_dns_cache() {
+        eprintln!("Warning: could not flush DNS cache: {}", e);
+    }
 }
 
 fn main() {
     let cli = Cli::parse();
 
-    // Ensure .focus directory and default domains file exist
+    if let Commands::Daemon = cli.command {
+        if let Err(e) = run_daemon() {
+            eprintln!("Error in focus daemon: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Ensure .focus directory and default profile exist
     if let Err(e) = ensure_focus_dir() {
         eprintln!("Error creating focus directory: {}", e);
         std::process::exit(1);
     }
 
+    recover_expired_schedule();
+
     let result = match cli.command {
-        Commands::On => focus_on(),
-        Commands::Off => focus_off(),
-        Commands::Edit => focus_edit(),
-        Commands::Status => focus_status(),
+        Commands::On {
+            for_duration,
+            until,
+            profile,
+        } => focus_on(profile, for_duration, until),
+        Commands::Off { profile } => focus_off(profile),
+        Commands::Edit { profile } => focus_edit(profile),
+        Commands::Status { profile } => focus_status(profile),
+        Commands::Extend { duration } => focus_extend(duration),
+        Commands::Restore => focus_restore(),
+        Commands::Sync => focus_sync(),
+        Commands::Daemon => unreachable!(),
     };
 
     if let Err(e) = result {
@@ -241,3 +1497,161 @@ fn main() {
         std::process::exit(1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_secs_combines_units() {
+        assert_eq!(parse_duration_secs("25m"), Ok(1500));
+        assert_eq!(parse_duration_secs("1h30m"), Ok(5400));
+        assert_eq!(parse_duration_secs("45s"), Ok(45));
+    }
+
+    #[test]
+    fn parse_duration_secs_rejects_malformed_input() {
+        assert!(parse_duration_secs("").is_err());
+        assert!(parse_duration_secs("30").is_err()); // missing unit
+        assert!(parse_duration_secs("30x").is_err()); // unknown unit
+    }
+
+    #[test]
+    fn parse_duration_secs_rejects_overflow_instead_of_panicking() {
+        assert!(parse_duration_secs("18446744073709551615h").is_err());
+        assert!(parse_duration_secs("99999999999999999999h").is_err());
+    }
+
+    #[test]
+    fn parse_clock_time_accepts_hm_and_hms() {
+        assert_eq!(parse_clock_time("17:00"), Some((17, 0, 0)));
+        assert_eq!(parse_clock_time("09:05:30"), Some((9, 5, 30)));
+    }
+
+    #[test]
+    fn parse_clock_time_rejects_out_of_range() {
+        assert_eq!(parse_clock_time("24:00"), None);
+        assert_eq!(parse_clock_time("12:60"), None);
+        assert_eq!(parse_clock_time("not-a-time"), None);
+    }
+
+    #[test]
+    fn days_from_civil_matches_known_epoch_offsets() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(2026, 7, 30), 20664);
+    }
+
+    #[test]
+    fn parse_utc_offset_accepts_both_date_and_powershell_forms() {
+        assert_eq!(parse_utc_offset("+0000"), Some(0));
+        assert_eq!(parse_utc_offset("-0500"), Some(-18000));
+        assert_eq!(parse_utc_offset("+05:30"), Some(19800));
+        assert_eq!(parse_utc_offset("garbage"), None);
+    }
+
+    #[test]
+    fn json_string_array_round_trips_through_parse() {
+        let values = vec!["work".to_string(), "has \"quotes\"".to_string(), "back\\slash".to_string()];
+        let encoded = json_string_array(&values);
+        let content = format!("{{\"profiles\":[{}]}}", encoded);
+        assert_eq!(parse_json_string_array(&content, "profiles"), values);
+    }
+
+    #[test]
+    fn parse_json_string_array_handles_empty_and_missing_key() {
+        assert_eq!(parse_json_string_array("{\"domains\":[]}", "domains"), Vec::<String>::new());
+        assert_eq!(parse_json_string_array("{\"domains\":[]}", "profiles"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn expand_domain_entry_expands_bare_apex_domains() {
+        let prefixes = vec!["www".to_string(), "api".to_string()];
+        assert_eq!(
+            expand_domain_entry("instagram.com", &prefixes),
+            vec!["instagram.com", "www.instagram.com", "api.instagram.com"]
+        );
+    }
+
+    #[test]
+    fn expand_domain_entry_expands_explicit_wildcard_entries() {
+        let prefixes = vec!["www".to_string()];
+        assert_eq!(
+            expand_domain_entry("*.instagram.com", &prefixes),
+            vec!["instagram.com", "www.instagram.com"]
+        );
+    }
+
+    #[test]
+    fn expand_domain_entry_leaves_explicit_subdomains_unexpanded() {
+        let prefixes = vec!["www".to_string()];
+        assert_eq!(
+            expand_domain_entry("www.instagram.com", &prefixes),
+            vec!["www.instagram.com"]
+        );
+        assert_eq!(
+            expand_domain_entry("a.b.instagram.com", &prefixes),
+            vec!["a.b.instagram.com"]
+        );
+    }
+
+    #[test]
+    fn parse_hosts_format_strips_ip_prefixes_and_comments() {
+        let raw = "\
+# a comment
+0.0.0.0 ads.example.com
+127.0.0.1 tracker.example.com
+
+bare-domain.example.com
+";
+        assert_eq!(
+            parse_hosts_format(raw),
+            vec![
+                "ads.example.com".to_string(),
+                "tracker.example.com".to_string(),
+                "bare-domain.example.com".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_hosts_format_ignores_blank_and_comment_only_input() {
+        assert_eq!(parse_hosts_format("\n# just a comment\n  \n"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn match_regex_entries_filters_corpus_by_pattern() {
+        let corpus = vec![
+            "instagram.com".to_string(),
+            "www.instagram.com".to_string(),
+            "example.com".to_string(),
+        ];
+        let matches = match_regex_entries(r"^(www\.)?instagram\.com$", &corpus).unwrap();
+        assert_eq!(matches, vec!["instagram.com".to_string(), "www.instagram.com".to_string()]);
+    }
+
+    #[test]
+    fn match_regex_entries_rejects_invalid_pattern() {
+        assert!(match_regex_entries("(unclosed", &[]).is_err());
+    }
+
+    #[test]
+    fn parse_profile_arg_splits_and_trims() {
+        assert_eq!(
+            parse_profile_arg(&Some("work, social".to_string())),
+            Ok(vec!["work".to_string(), "social".to_string()])
+        );
+    }
+
+    #[test]
+    fn parse_profile_arg_defaults_when_absent() {
+        assert_eq!(parse_profile_arg(&None), Ok(vec![DEFAULT_PROFILE.to_string()]));
+    }
+
+    #[test]
+    fn parse_profile_arg_rejects_blank_argument() {
+        assert!(parse_profile_arg(&Some(",".to_string())).is_err());
+        assert!(parse_profile_arg(&Some("   ".to_string())).is_err());
+        assert!(parse_profile_arg(&Some(String::new())).is_err());
+    }
+}